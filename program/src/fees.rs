@@ -0,0 +1,29 @@
+/**
+Serum-style SRM/MSRM fee discount schedule. The taker fee owed on a fill is scaled
+down based on how much SRM (or MSRM) the trading user holds in a referenced
+discount token account, mirroring the tiers used by Project Serum's dex.
+*/
+pub const BASE_TAKER_FEE_BPS: u64 = 30;
+
+const TIER_1000_SRM: u64 = 1_000;
+const TIER_10_000_SRM: u64 = 10_000;
+const TIER_100_000_SRM: u64 = 100_000;
+const TIER_1_000_000_SRM: u64 = 1_000_000;
+
+/**
+Selects the taker fee, in basis points, for a user holding `srm_balance` SRM (or
+any non-zero amount of MSRM, which always qualifies for the top tier).
+*/
+pub fn taker_fee_bps(srm_balance: u64, is_msrm: bool) -> u64 {
+    if is_msrm && srm_balance > 0 {
+        return 0;
+    }
+    let discount_bps = match srm_balance {
+        bal if bal >= TIER_1_000_000_SRM => 100,
+        bal if bal >= TIER_100_000_SRM => 60,
+        bal if bal >= TIER_10_000_SRM => 40,
+        bal if bal >= TIER_1000_SRM => 20,
+        _ => 0,
+    };
+    BASE_TAKER_FEE_BPS - BASE_TAKER_FEE_BPS * discount_bps / 100
+}
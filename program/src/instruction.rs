@@ -0,0 +1,20 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::processor::{
+    authorize_delegate, cancel_order, close_market, close_user_account, consume_events,
+    create_market, new_order, settle,
+};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum DexInstruction {
+    CreateMarket(create_market::Params),
+    NewOrder(new_order::Params),
+    ConsumeEvents(consume_events::Params),
+    CancelOrder(cancel_order::Params),
+    Settle(settle::Params),
+    CloseMarket(close_market::Params),
+    CancelOrderByClientId(cancel_order::ByClientIdParams),
+    AuthorizeDelegate(authorize_delegate::Params),
+    RevokeDelegate(authorize_delegate::RevokeParams),
+    CloseUserAccount(close_user_account::Params),
+}
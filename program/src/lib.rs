@@ -0,0 +1,5 @@
+pub mod fees;
+pub mod instruction;
+pub mod processor;
+pub mod state;
+pub mod utils;
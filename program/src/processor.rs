@@ -23,7 +23,10 @@ pub static MSRM_MINT: Pubkey = msrm_token::ID;
 
 ////////////////////////////////////////////////////////////
 
+pub mod authorize_delegate;
 pub mod cancel_order;
+pub mod close_market;
+pub mod close_user_account;
 pub mod consume_events;
 pub mod create_market;
 pub mod new_order;
@@ -63,6 +66,26 @@ impl Processor {
                 msg!("Instruction: Settle");
                 settle::process(program_id, accounts, params)?;
             }
+            DexInstruction::CloseMarket(params) => {
+                msg!("Instruction: Close Market");
+                close_market::process(program_id, accounts, params)?;
+            }
+            DexInstruction::CancelOrderByClientId(params) => {
+                msg!("Instruction: Cancel Order By Client Id");
+                cancel_order::process_by_client_id(program_id, accounts, params)?;
+            }
+            DexInstruction::AuthorizeDelegate(params) => {
+                msg!("Instruction: Authorize Delegate");
+                authorize_delegate::process(program_id, accounts, params)?;
+            }
+            DexInstruction::RevokeDelegate(params) => {
+                msg!("Instruction: Revoke Delegate");
+                authorize_delegate::process_revoke(program_id, accounts, params)?;
+            }
+            DexInstruction::CloseUserAccount(params) => {
+                msg!("Instruction: Close User Account");
+                close_user_account::process(program_id, accounts, params)?;
+            }
         }
         Ok(())
     }
@@ -0,0 +1,160 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    state::{AccountTag, DelegateSet, DexState},
+    utils::{check_account_key, check_signer},
+};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+/**
+The required arguments for an authorize_delegate instruction.
+*/
+pub struct Params {
+    pub delegate: Pubkey,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+/**
+The required arguments for a revoke_delegate instruction.
+*/
+pub struct RevokeParams {
+    pub delegate: Pubkey,
+}
+
+struct Accounts<'a, 'b: 'a> {
+    market: &'a AccountInfo<'b>,
+    delegate_set: &'a AccountInfo<'b>,
+    market_authority: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, 'b> {
+    pub fn parse(
+        _program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            market: next_account_info(accounts_iter)?,
+            delegate_set: next_account_info(accounts_iter)?,
+            market_authority: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+        check_signer(&a.market_authority).unwrap();
+        check_account_key(a.system_program, &solana_program::system_program::ID).unwrap();
+
+        Ok(a)
+    }
+
+    fn check_market_authority(&self) -> ProgramResult {
+        let market_state =
+            DexState::deserialize(&mut (&self.market.data.borrow() as &[u8]))?.check()?;
+        if market_state.market_authority != Some(*self.market_authority.key) {
+            msg!("The provided account is not this market's authority");
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(())
+    }
+
+    /**
+    Creates this market's delegate set PDA, funded and signed for by the market
+    authority, if it doesn't already exist.
+    */
+    fn create_delegate_set_if_needed(&self, program_id: &Pubkey) -> ProgramResult {
+        if !self.delegate_set.data_is_empty() {
+            return Ok(());
+        }
+
+        let (delegate_set_key, bump) = DelegateSet::find_address(program_id, self.market.key);
+        check_account_key(self.delegate_set, &delegate_set_key).unwrap();
+
+        let rent = Rent::get()?;
+        let create_account_instruction = system_instruction::create_account(
+            self.market_authority.key,
+            self.delegate_set.key,
+            rent.minimum_balance(DelegateSet::SIZE),
+            DelegateSet::SIZE as u64,
+            program_id,
+        );
+        invoke_signed(
+            &create_account_instruction,
+            &[self.market_authority.clone(), self.delegate_set.clone()],
+            &[&[
+                DelegateSet::SEED_PREFIX,
+                &self.market.key.to_bytes(),
+                &[bump],
+            ]],
+        )
+    }
+
+    /**
+    Validates the market authority signer and the delegate set PDA, then returns
+    the current delegate set, initializing a fresh empty one on first use.
+    */
+    fn load_delegate_set(&self, program_id: &Pubkey) -> Result<DelegateSet, ProgramError> {
+        let (delegate_set_key, _) = DelegateSet::find_address(program_id, self.market.key);
+        check_account_key(self.delegate_set, &delegate_set_key).unwrap();
+
+        let delegate_set =
+            DelegateSet::deserialize(&mut (&self.delegate_set.data.borrow() as &[u8]))?;
+        if delegate_set.tag == AccountTag::Uninitialized {
+            return Ok(DelegateSet {
+                tag: AccountTag::DelegateSet,
+                market: *self.market.key,
+                delegates: Vec::new(),
+            });
+        }
+        delegate_set.check()
+    }
+
+    fn write_delegate_set(&self, delegate_set: &DelegateSet) {
+        let mut data: &mut [u8] = &mut self.delegate_set.data.borrow_mut();
+        delegate_set.serialize(&mut data).unwrap();
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: Params,
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+    let Params { delegate } = params;
+
+    accounts.check_market_authority()?;
+    accounts.create_delegate_set_if_needed(program_id)?;
+
+    let mut delegate_set = accounts.load_delegate_set(program_id)?;
+    delegate_set.authorize(delegate)?;
+    accounts.write_delegate_set(&delegate_set);
+
+    Ok(())
+}
+
+pub(crate) fn process_revoke(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: RevokeParams,
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+    let RevokeParams { delegate } = params;
+
+    accounts.check_market_authority()?;
+
+    let mut delegate_set = accounts.load_delegate_set(program_id)?;
+    delegate_set.revoke(&delegate)?;
+    accounts.write_delegate_set(&delegate_set);
+
+    Ok(())
+}
@@ -14,8 +14,8 @@ use solana_program::{
 };
 
 use crate::{
-    state::{DexState, UserAccount},
-    utils::{check_account_key, check_signer},
+    state::{check_trade_authority, DelegateSet, DexState, UserAccount},
+    utils::check_account_key,
 };
 
 #[derive(BorshDeserialize, BorshSerialize)]
@@ -43,7 +43,10 @@ struct Accounts<'a, 'b: 'a> {
     bids: &'a AccountInfo<'b>,
     asks: &'a AccountInfo<'b>,
     user: &'a AccountInfo<'b>,
-    user_owner: &'a AccountInfo<'b>,
+    /// The user's own owner key, or an authorized market authority/delegate on
+    /// permissioned markets. See [`check_trade_authority`].
+    signer: &'a AccountInfo<'b>,
+    delegate_set: Option<&'a AccountInfo<'b>>,
 }
 
 impl<'a, 'b: 'a> Accounts<'a, 'b> {
@@ -61,47 +64,90 @@ impl<'a, 'b: 'a> Accounts<'a, 'b> {
             bids: next_account_info(accounts_iter)?,
             asks: next_account_info(accounts_iter)?,
             user: next_account_info(accounts_iter)?,
-            user_owner: next_account_info(accounts_iter)?,
+            signer: next_account_info(accounts_iter)?,
+            delegate_set: accounts_iter.next(),
         };
-        check_signer(&a.user_owner).unwrap();
 
         Ok(a)
     }
 
-    pub fn load_user_account(&self) -> Result<UserAccount<'b>, ProgramError> {
+    pub fn load_user_account(
+        &self,
+        program_id: &Pubkey,
+        market_authority: Option<Pubkey>,
+    ) -> Result<UserAccount<'b>, ProgramError> {
         let user_account = UserAccount::parse(&self.user)?;
-        if &user_account.header.owner != self.user_owner.key {
-            msg!("Invalid user account owner provided!");
-            return Err(ProgramError::InvalidArgument);
-        }
         if &user_account.header.market != self.market.key {
             msg!("The provided user account doesn't match the current market");
             return Err(ProgramError::InvalidArgument);
         };
+
+        let delegate_set = DelegateSet::load_optional(program_id, self.market.key, self.delegate_set)?;
+        check_trade_authority(
+            market_authority,
+            &user_account.header.owner,
+            self.signer,
+            delegate_set.as_ref(),
+        )?;
+
         Ok(user_account)
     }
 }
 
+#[derive(BorshDeserialize, BorshSerialize)]
+/**
+The required arguments for a cancel_order_by_client_id instruction.
+*/
+pub struct ByClientIdParams {
+    client_order_id: u64,
+}
+
 pub(crate) fn process(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     params: Params,
 ) -> ProgramResult {
     let accounts = Accounts::parse(program_id, accounts)?;
-
     let Params { order_index } = params;
 
     let market_state =
         DexState::deserialize(&mut (&accounts.market.data.borrow() as &[u8]))?.check()?;
+    let mut user_account =
+        accounts.load_user_account(program_id, market_state.market_authority)?;
+
+    cancel(program_id, &accounts, market_state, &mut user_account, order_index as usize)
+}
+
+pub(crate) fn process_by_client_id(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: ByClientIdParams,
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+    let ByClientIdParams { client_order_id } = params;
+
+    let market_state =
+        DexState::deserialize(&mut (&accounts.market.data.borrow() as &[u8]))?.check()?;
+    let mut user_account =
+        accounts.load_user_account(program_id, market_state.market_authority)?;
+    let order_index = user_account.find_order_index_by_client_id(client_order_id)?;
 
-    let mut user_account = accounts.load_user_account()?;
+    cancel(program_id, &accounts, market_state, &mut user_account, order_index)
+}
 
+fn cancel(
+    program_id: &Pubkey,
+    accounts: &Accounts,
+    market_state: DexState,
+    user_account: &mut UserAccount,
+    order_index: usize,
+) -> ProgramResult {
     let mut market_data: &mut [u8] = &mut accounts.market.data.borrow_mut();
     market_state.serialize(&mut market_data).unwrap();
 
-    check_accounts(program_id, &market_state, &accounts).unwrap();
+    check_accounts(program_id, &market_state, accounts).unwrap();
 
-    let order_id = user_account.read_order(order_index as usize)?;
+    let order_id = user_account.read_order(order_index)?;
 
     let cancel_order_instruction = agnostic_orderbook::instruction::cancel_order(
         *accounts.aaob_program.key,
@@ -168,7 +214,7 @@ pub(crate) fn process(
         }
     };
 
-    user_account.remove_order(order_index as usize)?;
+    user_account.remove_order(order_index)?;
 
     user_account.write();
 
@@ -0,0 +1,188 @@
+use agnostic_orderbook::state::{critbit::SlabHeader, EventQueueHeader};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    state::{sweep_lamports, AccountTag, DexState},
+    utils::{check_account_key, check_signer},
+};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+/**
+The required arguments for a close_market instruction.
+*/
+pub struct Params {}
+
+struct Accounts<'a, 'b: 'a> {
+    spl_token_program: &'a AccountInfo<'b>,
+    aaob_program: &'a AccountInfo<'b>,
+    market: &'a AccountInfo<'b>,
+    market_signer: &'a AccountInfo<'b>,
+    orderbook: &'a AccountInfo<'b>,
+    event_queue: &'a AccountInfo<'b>,
+    bids: &'a AccountInfo<'b>,
+    asks: &'a AccountInfo<'b>,
+    base_vault: &'a AccountInfo<'b>,
+    quote_vault: &'a AccountInfo<'b>,
+    destination: &'a AccountInfo<'b>,
+    admin: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, 'b> {
+    pub fn parse(
+        _program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            spl_token_program: next_account_info(accounts_iter)?,
+            aaob_program: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            market_signer: next_account_info(accounts_iter)?,
+            orderbook: next_account_info(accounts_iter)?,
+            event_queue: next_account_info(accounts_iter)?,
+            bids: next_account_info(accounts_iter)?,
+            asks: next_account_info(accounts_iter)?,
+            base_vault: next_account_info(accounts_iter)?,
+            quote_vault: next_account_info(accounts_iter)?,
+            destination: next_account_info(accounts_iter)?,
+            admin: next_account_info(accounts_iter)?,
+        };
+        check_signer(&a.admin).unwrap();
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _params: Params,
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let market_state =
+        DexState::deserialize(&mut (&accounts.market.data.borrow() as &[u8]))?.check()?;
+
+    check_accounts(program_id, &market_state, &accounts).unwrap();
+
+    let event_queue_header =
+        EventQueueHeader::deserialize(&mut (&accounts.event_queue.data.borrow() as &[u8]))?;
+    if event_queue_header.count != 0 {
+        msg!("The event queue must be empty before the market can be closed");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let bids_header = SlabHeader::deserialize(&mut (&accounts.bids.data.borrow() as &[u8]))?;
+    let asks_header = SlabHeader::deserialize(&mut (&accounts.asks.data.borrow() as &[u8]))?;
+    if bids_header.leaf_count != 0 || asks_header.leaf_count != 0 {
+        msg!("The orderbook must be empty before the market can be closed");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let base_vault = spl_token::state::Account::unpack(&accounts.base_vault.data.borrow())?;
+    let quote_vault = spl_token::state::Account::unpack(&accounts.quote_vault.data.borrow())?;
+    if base_vault.amount != 0 || quote_vault.amount != 0 {
+        msg!("The market's vaults must be emptied before the market can be closed");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let close_market_instruction = agnostic_orderbook::instruction::close_market(
+        *accounts.aaob_program.key,
+        *accounts.orderbook.key,
+        *accounts.market_signer.key,
+        *accounts.event_queue.key,
+        *accounts.bids.key,
+        *accounts.asks.key,
+        accounts.destination.key,
+    );
+
+    invoke_signed(
+        &close_market_instruction,
+        &[
+            accounts.aaob_program.clone(),
+            accounts.orderbook.clone(),
+            accounts.event_queue.clone(),
+            accounts.bids.clone(),
+            accounts.asks.clone(),
+            accounts.market_signer.clone(),
+            accounts.destination.clone(),
+        ],
+        &[&[
+            &accounts.market.key.to_bytes(),
+            &[market_state.signer_nonce],
+        ]],
+    )?;
+
+    let signer_seeds: &[&[u8]] = &[&accounts.market.key.to_bytes(), &[market_state.signer_nonce]];
+
+    for vault in [accounts.base_vault, accounts.quote_vault] {
+        let close_vault_instruction = spl_token::instruction::close_account(
+            accounts.spl_token_program.key,
+            vault.key,
+            accounts.destination.key,
+            accounts.market_signer.key,
+            &[],
+        )?;
+        invoke_signed(
+            &close_vault_instruction,
+            &[
+                vault.clone(),
+                accounts.destination.clone(),
+                accounts.market_signer.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+    }
+
+    let market_state = DexState {
+        tag: AccountTag::Closed,
+        signer_nonce: 0,
+        base_mint: Pubkey::default(),
+        quote_mint: Pubkey::default(),
+        base_vault: Pubkey::default(),
+        quote_vault: Pubkey::default(),
+        orderbook: Pubkey::default(),
+        aaob_program: Pubkey::default(),
+        admin: Pubkey::default(),
+        fee_accumulator: Pubkey::default(),
+        market_authority: None,
+    };
+    let mut market_data: &mut [u8] = &mut accounts.market.data.borrow_mut();
+    market_state.serialize(&mut market_data).unwrap();
+
+    sweep_lamports(accounts.market, accounts.destination)?;
+
+    Ok(())
+}
+
+fn check_accounts(
+    program_id: &Pubkey,
+    market_state: &DexState,
+    accounts: &Accounts,
+) -> ProgramResult {
+    let market_signer = Pubkey::create_program_address(
+        &[
+            &accounts.market.key.to_bytes(),
+            &[market_state.signer_nonce],
+        ],
+        program_id,
+    )?;
+    check_account_key(accounts.market_signer, &market_signer).unwrap();
+    check_account_key(accounts.orderbook, &market_state.orderbook).unwrap();
+    check_account_key(accounts.aaob_program, &market_state.aaob_program).unwrap();
+    check_account_key(accounts.base_vault, &market_state.base_vault).unwrap();
+    check_account_key(accounts.quote_vault, &market_state.quote_vault).unwrap();
+    check_account_key(accounts.spl_token_program, &spl_token::ID).unwrap();
+    check_account_key(accounts.admin, &market_state.admin).unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    state::{sweep_lamports, UserAccount},
+    utils::check_signer,
+};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+/**
+The required arguments for a close_user_account instruction.
+*/
+pub struct Params {}
+
+struct Accounts<'a, 'b: 'a> {
+    user: &'a AccountInfo<'b>,
+    user_owner: &'a AccountInfo<'b>,
+    destination: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, 'b> {
+    pub fn parse(
+        _program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            user: next_account_info(accounts_iter)?,
+            user_owner: next_account_info(accounts_iter)?,
+            destination: next_account_info(accounts_iter)?,
+        };
+        check_signer(&a.user_owner).unwrap();
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _params: Params,
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let mut user_account = UserAccount::parse(&accounts.user)?;
+    if &user_account.header.owner != accounts.user_owner.key {
+        msg!("Invalid user account owner provided!");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    user_account.close()?;
+    user_account.write();
+
+    sweep_lamports(accounts.user, accounts.destination)?;
+
+    Ok(())
+}
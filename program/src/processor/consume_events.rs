@@ -0,0 +1,347 @@
+use std::rc::Rc;
+
+use agnostic_orderbook::state::{
+    get_price_from_order_id, Event, EventQueue, EventQueueHeader, Side,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    fees,
+    processor::{MSRM_MINT, SRM_MINT},
+    state::{DexState, UserAccount},
+    utils::check_account_key,
+};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+/**
+The required arguments for a consume_events instruction.
+*/
+pub struct Params {
+    pub max_iterations: u64,
+    /// Whether the account right after `fee_accumulator` is an SRM/MSRM discount
+    /// token account, rather than the first of the trailing `user_accounts`.
+    pub has_srm_discount_account: bool,
+}
+
+struct Accounts<'a, 'b: 'a> {
+    spl_token_program: &'a AccountInfo<'b>,
+    aaob_program: &'a AccountInfo<'b>,
+    market: &'a AccountInfo<'b>,
+    market_signer: &'a AccountInfo<'b>,
+    orderbook: &'a AccountInfo<'b>,
+    event_queue: &'a AccountInfo<'b>,
+    quote_vault: &'a AccountInfo<'b>,
+    fee_accumulator: &'a AccountInfo<'b>,
+    srm_discount_account: Option<&'a AccountInfo<'b>>,
+    /// The user accounts of every participant (maker or taker) whose fills should
+    /// be credited this call. Mirrors how Serum's `consume_events` takes the
+    /// referenced open orders accounts as remaining accounts.
+    user_accounts: Vec<&'a AccountInfo<'b>>,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, 'b> {
+    pub fn parse(
+        _program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+        has_srm_discount_account: bool,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            spl_token_program: next_account_info(accounts_iter)?,
+            aaob_program: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            market_signer: next_account_info(accounts_iter)?,
+            orderbook: next_account_info(accounts_iter)?,
+            event_queue: next_account_info(accounts_iter)?,
+            quote_vault: next_account_info(accounts_iter)?,
+            fee_accumulator: next_account_info(accounts_iter)?,
+            srm_discount_account: if has_srm_discount_account {
+                Some(next_account_info(accounts_iter)?)
+            } else {
+                None
+            },
+            user_accounts: accounts_iter.collect(),
+        };
+        if a.user_accounts.is_empty() {
+            msg!("At least one user account must be provided");
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        Ok(a)
+    }
+}
+
+/**
+Reads a taker's SRM/MSRM discount token account, if one was provided, and
+returns the taker fee (in basis points) it entitles `user_owner` to. Falls back
+to the undiscounted base fee when no account was supplied, or when the account
+belongs to a different trader than `user_owner`.
+*/
+fn resolve_taker_fee_bps(
+    srm_discount_account: Option<&AccountInfo>,
+    user_owner: &Pubkey,
+) -> Result<u64, ProgramError> {
+    let discount_account = match srm_discount_account {
+        Some(a) => a,
+        None => return Ok(fees::BASE_TAKER_FEE_BPS),
+    };
+
+    let token_account = spl_token::state::Account::unpack(&discount_account.data.borrow())?;
+    if &token_account.owner != user_owner {
+        return Ok(fees::BASE_TAKER_FEE_BPS);
+    }
+    let is_msrm = token_account.mint == MSRM_MINT;
+    if !is_msrm && token_account.mint != SRM_MINT {
+        msg!("The discount account's mint must be SRM or MSRM");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    Ok(fees::taker_fee_bps(token_account.amount, is_msrm))
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: Params,
+) -> ProgramResult {
+    let Params {
+        max_iterations,
+        has_srm_discount_account,
+    } = params;
+    let accounts = Accounts::parse(program_id, accounts, has_srm_discount_account)?;
+
+    let market_state =
+        DexState::deserialize(&mut (&accounts.market.data.borrow() as &[u8]))?.check()?;
+    check_account_key(accounts.orderbook, &market_state.orderbook).unwrap();
+    check_account_key(accounts.aaob_program, &market_state.aaob_program).unwrap();
+    check_account_key(accounts.quote_vault, &market_state.quote_vault).unwrap();
+    check_account_key(accounts.fee_accumulator, &market_state.fee_accumulator).unwrap();
+
+    let mut user_accounts = accounts
+        .user_accounts
+        .iter()
+        .map(|a| {
+            let user_account = UserAccount::parse(a)?;
+            if user_account.header.market != *accounts.market.key {
+                msg!("A provided user account doesn't match the current market");
+                return Err(ProgramError::InvalidArgument);
+            }
+            Ok(user_account)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let event_queue_header =
+        EventQueueHeader::deserialize(&mut (&accounts.event_queue.data.borrow() as &[u8]))?;
+    let event_queue = EventQueue::new(event_queue_header, Rc::clone(&accounts.event_queue.data), 32);
+
+    let mut entries_consumed = 0u64;
+    let mut fee_owed = 0u64;
+
+    for i in 0..event_queue.len().min(max_iterations as usize) {
+        let event = event_queue.peek_at(i).unwrap();
+        match event {
+            Event::Fill {
+                taker_side,
+                quote_size,
+                base_size,
+                maker_callback_info,
+                taker_callback_info,
+                ..
+            } => {
+                let taker_index = user_accounts
+                    .iter()
+                    .position(|u| u.header.owner.to_bytes() == taker_callback_info);
+                let maker_index = user_accounts
+                    .iter()
+                    .position(|u| u.header.owner.to_bytes() == maker_callback_info);
+                let (taker_index, maker_index) = match (taker_index, maker_index) {
+                    (Some(taker_index), Some(maker_index)) => (taker_index, maker_index),
+                    _ => {
+                        msg!(
+                            "Stopping after {} entries: an account for the next event was not provided",
+                            entries_consumed
+                        );
+                        break;
+                    }
+                };
+
+                let taker_fee_bps = resolve_taker_fee_bps(
+                    accounts.srm_discount_account,
+                    &user_accounts[taker_index].header.owner,
+                )?;
+                let fee = quote_size
+                    .checked_mul(taker_fee_bps)
+                    .unwrap()
+                    .checked_div(10_000)
+                    .unwrap();
+                fee_owed = fee_owed.checked_add(fee).unwrap();
+                match taker_side {
+                    Side::Bid => {
+                        user_accounts[taker_index].header.base_token_free = user_accounts
+                            [taker_index]
+                            .header
+                            .base_token_free
+                            .checked_add(base_size)
+                            .unwrap();
+                    }
+                    Side::Ask => {
+                        user_accounts[taker_index].header.quote_token_free = user_accounts
+                            [taker_index]
+                            .header
+                            .quote_token_free
+                            .checked_add(quote_size.checked_sub(fee).unwrap())
+                            .unwrap();
+                    }
+                }
+
+                match taker_side {
+                    Side::Bid => {
+                        user_accounts[maker_index].header.quote_token_free = user_accounts
+                            [maker_index]
+                            .header
+                            .quote_token_free
+                            .checked_add(quote_size)
+                            .unwrap();
+                    }
+                    Side::Ask => {
+                        user_accounts[maker_index].header.base_token_free = user_accounts
+                            [maker_index]
+                            .header
+                            .base_token_free
+                            .checked_add(base_size)
+                            .unwrap();
+                    }
+                }
+            }
+            Event::Out {
+                side,
+                order_id,
+                base_size,
+                delete,
+                callback_info,
+                ..
+            } => {
+                let index = user_accounts
+                    .iter()
+                    .position(|u| u.header.owner.to_bytes() == callback_info);
+                let index = match index {
+                    Some(index) => index,
+                    None => {
+                        msg!(
+                            "Stopping after {} entries: an account for the next event was not provided",
+                            entries_consumed
+                        );
+                        break;
+                    }
+                };
+
+                let user_account = &mut user_accounts[index];
+                let order_index = match user_account.find_order_index_by_id(order_id) {
+                    Ok(order_index) => order_index,
+                    Err(_) => {
+                        msg!(
+                            "Stopping after {} entries: the canceled order was not found on its owner's user account",
+                            entries_consumed
+                        );
+                        break;
+                    }
+                };
+
+                // An `Out` event only ever carries the order's remaining base
+                // quantity; for a bid the locked quote amount it released is
+                // recovered from the price baked into its own order id, same as
+                // `get_side_from_order_id` does for the side.
+                match side {
+                    Side::Bid => {
+                        let quote_size = get_price_from_order_id(order_id)
+                            .checked_mul(base_size)
+                            .unwrap();
+                        user_account.header.quote_token_free = user_account
+                            .header
+                            .quote_token_free
+                            .checked_add(quote_size)
+                            .unwrap();
+                        user_account.header.quote_token_locked = user_account
+                            .header
+                            .quote_token_locked
+                            .checked_sub(quote_size)
+                            .unwrap();
+                    }
+                    Side::Ask => {
+                        user_account.header.base_token_free = user_account
+                            .header
+                            .base_token_free
+                            .checked_add(base_size)
+                            .unwrap();
+                        user_account.header.base_token_locked = user_account
+                            .header
+                            .base_token_locked
+                            .checked_sub(base_size)
+                            .unwrap();
+                    }
+                }
+                // A partial reduction (e.g. from a self-trade decrement-take)
+                // leaves the order resting on the book with a smaller size; only
+                // a full cancellation frees its tracking slot.
+                if delete {
+                    user_account.remove_order(order_index)?;
+                }
+            }
+            _ => {}
+        }
+        entries_consumed += 1;
+    }
+
+    if fee_owed > 0 {
+        let transfer_fee_instruction = spl_token::instruction::transfer(
+            accounts.spl_token_program.key,
+            accounts.quote_vault.key,
+            accounts.fee_accumulator.key,
+            accounts.market_signer.key,
+            &[],
+            fee_owed,
+        )?;
+        invoke_signed(
+            &transfer_fee_instruction,
+            &[
+                accounts.quote_vault.clone(),
+                accounts.fee_accumulator.clone(),
+                accounts.market_signer.clone(),
+            ],
+            &[&[
+                &accounts.market.key.to_bytes(),
+                &[market_state.signer_nonce],
+            ]],
+        )?;
+    }
+
+    let consume_events_instruction = agnostic_orderbook::instruction::consume_events(
+        *accounts.aaob_program.key,
+        *accounts.orderbook.key,
+        *accounts.event_queue.key,
+        accounts.aaob_program.key,
+        &[],
+        agnostic_orderbook::instruction::consume_events::Params {
+            number_of_entries_to_consume: entries_consumed,
+        },
+    );
+    solana_program::program::invoke(
+        &consume_events_instruction,
+        &[accounts.aaob_program.clone(), accounts.event_queue.clone()],
+    )?;
+
+    for user_account in user_accounts {
+        user_account.write();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use crate::state::{AccountTag, DexState};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+/**
+The required arguments for a create_market instruction.
+*/
+pub struct Params {
+    pub signer_nonce: u8,
+    pub market_authority: Option<Pubkey>,
+}
+
+struct Accounts<'a, 'b: 'a> {
+    market: &'a AccountInfo<'b>,
+    orderbook: &'a AccountInfo<'b>,
+    base_vault: &'a AccountInfo<'b>,
+    quote_vault: &'a AccountInfo<'b>,
+    aaob_program: &'a AccountInfo<'b>,
+    admin: &'a AccountInfo<'b>,
+    fee_accumulator: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, 'b> {
+    pub fn parse(
+        _program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, solana_program::program_error::ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            market: next_account_info(accounts_iter)?,
+            orderbook: next_account_info(accounts_iter)?,
+            base_vault: next_account_info(accounts_iter)?,
+            quote_vault: next_account_info(accounts_iter)?,
+            aaob_program: next_account_info(accounts_iter)?,
+            admin: next_account_info(accounts_iter)?,
+            fee_accumulator: next_account_info(accounts_iter)?,
+        })
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: Params,
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let Params {
+        signer_nonce,
+        market_authority,
+    } = params;
+
+    let base_vault = spl_token::state::Account::unpack(&accounts.base_vault.data.borrow())?;
+    let quote_vault = spl_token::state::Account::unpack(&accounts.quote_vault.data.borrow())?;
+
+    let market_state = DexState {
+        tag: AccountTag::DexState,
+        signer_nonce,
+        base_mint: base_vault.mint,
+        quote_mint: quote_vault.mint,
+        base_vault: *accounts.base_vault.key,
+        quote_vault: *accounts.quote_vault.key,
+        orderbook: *accounts.orderbook.key,
+        aaob_program: *accounts.aaob_program.key,
+        admin: *accounts.admin.key,
+        fee_accumulator: *accounts.fee_accumulator.key,
+        market_authority,
+    };
+
+    let mut market_data: &mut [u8] = &mut accounts.market.data.borrow_mut();
+    market_state.serialize(&mut market_data)?;
+
+    Ok(())
+}
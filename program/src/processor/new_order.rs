@@ -0,0 +1,151 @@
+use std::rc::Rc;
+
+use agnostic_orderbook::state::{EventQueue, EventQueueHeader, OrderSummary};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    processor::cancel_order::OrderType,
+    state::{check_trade_authority, DelegateSet, DexState, SelfTradeBehavior, UserAccount},
+    utils::check_account_key,
+};
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct Params {
+    pub side: agnostic_orderbook::state::Side,
+    pub limit_price: u64,
+    pub max_base_qty: u64,
+    pub max_quote_qty: u64,
+    pub order_type: OrderType,
+    pub self_trade_behavior: SelfTradeBehavior,
+    pub match_limit: u64,
+    pub client_order_id: u64,
+}
+
+struct Accounts<'a, 'b: 'a> {
+    aaob_program: &'a AccountInfo<'b>,
+    market: &'a AccountInfo<'b>,
+    market_signer: &'a AccountInfo<'b>,
+    orderbook: &'a AccountInfo<'b>,
+    event_queue: &'a AccountInfo<'b>,
+    bids: &'a AccountInfo<'b>,
+    asks: &'a AccountInfo<'b>,
+    user: &'a AccountInfo<'b>,
+    /// The user's own owner key, or an authorized market authority/delegate on
+    /// permissioned markets. See [`check_trade_authority`].
+    signer: &'a AccountInfo<'b>,
+    delegate_set: Option<&'a AccountInfo<'b>>,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, 'b> {
+    pub fn parse(
+        _program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            aaob_program: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            market_signer: next_account_info(accounts_iter)?,
+            orderbook: next_account_info(accounts_iter)?,
+            event_queue: next_account_info(accounts_iter)?,
+            bids: next_account_info(accounts_iter)?,
+            asks: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            signer: next_account_info(accounts_iter)?,
+            delegate_set: accounts_iter.next(),
+        };
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: Params,
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let market_state = DexState::deserialize(&mut (&accounts.market.data.borrow() as &[u8]))?.check()?;
+
+    check_account_key(accounts.orderbook, &market_state.orderbook).unwrap();
+    check_account_key(accounts.aaob_program, &market_state.aaob_program).unwrap();
+
+    let mut user_account = UserAccount::parse(&accounts.user)?;
+    if user_account.header.market != *accounts.market.key {
+        msg!("The provided user account doesn't match the current market");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let delegate_set =
+        DelegateSet::load_optional(program_id, accounts.market.key, accounts.delegate_set)?;
+    check_trade_authority(
+        market_state.market_authority,
+        &user_account.header.owner,
+        accounts.signer,
+        delegate_set.as_ref(),
+    )?;
+
+    let callback_info = user_account.header.owner.to_bytes();
+
+    let new_order_instruction = agnostic_orderbook::instruction::new_order(
+        *accounts.aaob_program.key,
+        *accounts.orderbook.key,
+        *accounts.market_signer.key,
+        *accounts.event_queue.key,
+        *accounts.bids.key,
+        *accounts.asks.key,
+        agnostic_orderbook::instruction::new_order::Params {
+            max_base_qty: params.max_base_qty,
+            max_quote_qty: params.max_quote_qty,
+            limit_price: params.limit_price,
+            side: params.side,
+            match_limit: params.match_limit,
+            callback_info,
+            post_only: matches!(params.order_type, OrderType::PostOnly),
+            post_allowed: !matches!(params.order_type, OrderType::ImmediateOrCancel),
+            self_trade_behavior: params.self_trade_behavior.into(),
+        },
+    );
+
+    invoke_signed(
+        &new_order_instruction,
+        &[
+            accounts.aaob_program.clone(),
+            accounts.orderbook.clone(),
+            accounts.event_queue.clone(),
+            accounts.bids.clone(),
+            accounts.asks.clone(),
+            accounts.market_signer.clone(),
+        ],
+        &[&[
+            &accounts.market.key.to_bytes(),
+            &[market_state.signer_nonce],
+        ]],
+    )?;
+
+    let event_queue_header =
+        EventQueueHeader::deserialize(&mut (&accounts.event_queue.data.borrow() as &[u8]))?;
+    let event_queue = EventQueue::new(
+        event_queue_header,
+        Rc::clone(&accounts.event_queue.data),
+        32,
+    );
+    let order_summary: OrderSummary = event_queue.read_register().unwrap().unwrap();
+
+    if let Some(order_id) = order_summary.posted_order_id {
+        user_account.add_order(order_id, params.client_order_id)?;
+    }
+
+    user_account.write();
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    state::{DexState, UserAccount},
+    utils::{check_account_key, check_signer},
+};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+/**
+The required arguments for a settle instruction.
+*/
+pub struct Params {}
+
+struct Accounts<'a, 'b: 'a> {
+    spl_token_program: &'a AccountInfo<'b>,
+    market: &'a AccountInfo<'b>,
+    market_signer: &'a AccountInfo<'b>,
+    base_vault: &'a AccountInfo<'b>,
+    quote_vault: &'a AccountInfo<'b>,
+    user: &'a AccountInfo<'b>,
+    user_owner: &'a AccountInfo<'b>,
+    destination_base_account: &'a AccountInfo<'b>,
+    destination_quote_account: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, 'b> {
+    pub fn parse(
+        _program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let a = Self {
+            spl_token_program: next_account_info(accounts_iter)?,
+            market: next_account_info(accounts_iter)?,
+            market_signer: next_account_info(accounts_iter)?,
+            base_vault: next_account_info(accounts_iter)?,
+            quote_vault: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            user_owner: next_account_info(accounts_iter)?,
+            destination_base_account: next_account_info(accounts_iter)?,
+            destination_quote_account: next_account_info(accounts_iter)?,
+        };
+        check_signer(&a.user_owner).unwrap();
+
+        Ok(a)
+    }
+}
+
+pub(crate) fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _params: Params,
+) -> ProgramResult {
+    let accounts = Accounts::parse(program_id, accounts)?;
+
+    let market_state = DexState::deserialize(&mut (&accounts.market.data.borrow() as &[u8]))?.check()?;
+    check_account_key(accounts.base_vault, &market_state.base_vault).unwrap();
+    check_account_key(accounts.quote_vault, &market_state.quote_vault).unwrap();
+
+    let mut user_account = UserAccount::parse(&accounts.user)?;
+    if &user_account.header.owner != accounts.user_owner.key {
+        msg!("Invalid user account owner provided!");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let signer_seeds: &[&[u8]] = &[&accounts.market.key.to_bytes(), &[market_state.signer_nonce]];
+
+    if user_account.header.base_token_free > 0 {
+        let transfer_instruction = spl_token::instruction::transfer(
+            accounts.spl_token_program.key,
+            accounts.base_vault.key,
+            accounts.destination_base_account.key,
+            accounts.market_signer.key,
+            &[],
+            user_account.header.base_token_free,
+        )?;
+        invoke_signed(
+            &transfer_instruction,
+            &[
+                accounts.base_vault.clone(),
+                accounts.destination_base_account.clone(),
+                accounts.market_signer.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        user_account.header.base_token_free = 0;
+    }
+
+    if user_account.header.quote_token_free > 0 {
+        let transfer_instruction = spl_token::instruction::transfer(
+            accounts.spl_token_program.key,
+            accounts.quote_vault.key,
+            accounts.destination_quote_account.key,
+            accounts.market_signer.key,
+            &[],
+            user_account.header.quote_token_free,
+        )?;
+        invoke_signed(
+            &transfer_instruction,
+            &[
+                accounts.quote_vault.clone(),
+                accounts.destination_quote_account.clone(),
+                accounts.market_signer.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        user_account.header.quote_token_free = 0;
+    }
+
+    user_account.write();
+
+    Ok(())
+}
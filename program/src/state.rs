@@ -0,0 +1,363 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey};
+
+pub const MAX_USER_ORDERS: usize = 64;
+pub const MAX_DELEGATES: usize = 16;
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AccountTag {
+    Uninitialized,
+    DexState,
+    UserAccount,
+    Closed,
+    DelegateSet,
+}
+
+/**
+Governs what happens when an incoming order would match against a resting order
+owned by the same trader. Self-trades are identified by comparing the 32-byte
+callback id (the user account key) of the resting order against the taker's.
+*/
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug)]
+pub enum SelfTradeBehavior {
+    DecrementTake,
+    CancelProvide,
+    AbortTransaction,
+}
+
+impl Default for SelfTradeBehavior {
+    fn default() -> Self {
+        Self::DecrementTake
+    }
+}
+
+impl From<SelfTradeBehavior> for agnostic_orderbook::state::SelfTradeBehavior {
+    fn from(behavior: SelfTradeBehavior) -> Self {
+        match behavior {
+            SelfTradeBehavior::DecrementTake => Self::DecrementTake,
+            SelfTradeBehavior::CancelProvide => Self::CancelProvide,
+            SelfTradeBehavior::AbortTransaction => Self::AbortTransaction,
+        }
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+/**
+The central market account. One is created per orderbook by `create_market` and
+referenced by every other instruction.
+*/
+pub struct DexState {
+    pub tag: AccountTag,
+    pub signer_nonce: u8,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub orderbook: Pubkey,
+    pub aaob_program: Pubkey,
+    pub admin: Pubkey,
+    pub fee_accumulator: Pubkey,
+    /**
+    When set, `new_order` and `cancel_order` require an additional signature from
+    this authority (or a delegate it has authorized via [`DelegateSet`]), enabling
+    KYC-gated or fund-managed markets. Markets created without one behave exactly
+    as an unpermissioned market.
+    */
+    pub market_authority: Option<Pubkey>,
+}
+
+impl DexState {
+    pub fn check(self) -> Result<Self, ProgramError> {
+        if self.tag != AccountTag::DexState {
+            msg!("Invalid market account tag");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(self)
+    }
+}
+
+/**
+The set of delegate pubkeys a market's authority has whitelisted to sign
+`new_order`/`cancel_order` on behalf of any user, for permissioned markets. Stored
+in a PDA derived from `["delegates", market]`.
+*/
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct DelegateSet {
+    pub tag: AccountTag,
+    pub market: Pubkey,
+    pub delegates: Vec<Pubkey>,
+}
+
+impl DelegateSet {
+    pub const SEED_PREFIX: &'static [u8] = b"delegates";
+    /// Worst-case serialized size (tag + market + a full `delegates` vec), used to size
+    /// the PDA when it's created by [`crate::processor::authorize_delegate`].
+    pub const SIZE: usize = 1 + 32 + 4 + 32 * MAX_DELEGATES;
+
+    pub fn check(self) -> Result<Self, ProgramError> {
+        if self.tag != AccountTag::DelegateSet {
+            msg!("Invalid delegate set account tag");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(self)
+    }
+
+    /**
+    Derives this market's delegate set PDA, as seeded in [`DelegateSet::SEED_PREFIX`].
+    */
+    pub fn find_address(program_id: &Pubkey, market: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED_PREFIX, &market.to_bytes()], program_id)
+    }
+
+    pub fn is_authorized(&self, delegate: &Pubkey) -> bool {
+        self.delegates.iter().any(|d| d == delegate)
+    }
+
+    /**
+    Validates and loads a market's delegate set, if one was provided. Used by
+    `new_order`/`cancel_order` to resolve the delegate set referenced by a
+    permissioned market, ahead of a [`check_trade_authority`] call.
+    */
+    pub fn load_optional(
+        program_id: &Pubkey,
+        market: &Pubkey,
+        account: Option<&AccountInfo>,
+    ) -> Result<Option<Self>, ProgramError> {
+        let account = match account {
+            Some(a) => a,
+            None => return Ok(None),
+        };
+        let (delegate_set_key, _) = Self::find_address(program_id, market);
+        crate::utils::check_account_key(account, &delegate_set_key).unwrap();
+        let delegate_set = Self::deserialize(&mut (&account.data.borrow() as &[u8]))?.check()?;
+        Ok(Some(delegate_set))
+    }
+
+    pub fn authorize(&mut self, delegate: Pubkey) -> Result<(), ProgramError> {
+        if self.is_authorized(&delegate) {
+            msg!("This delegate is already authorized");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if self.delegates.len() >= MAX_DELEGATES {
+            msg!("This market cannot authorize any more delegates");
+            return Err(ProgramError::InvalidArgument);
+        }
+        self.delegates.push(delegate);
+        Ok(())
+    }
+
+    pub fn revoke(&mut self, delegate: &Pubkey) -> Result<(), ProgramError> {
+        let index = self
+            .delegates
+            .iter()
+            .position(|d| d == delegate)
+            .ok_or_else(|| {
+                msg!("This delegate is not authorized");
+                ProgramError::InvalidArgument
+            })?;
+        self.delegates.remove(index);
+        Ok(())
+    }
+}
+
+/**
+Verifies that `signer` is allowed to act on behalf of a user of this market: either
+the user's own owner key, the market authority, or a delegate the authority has
+whitelisted. Markets without a `market_authority` only ever require the owner's
+signature, preserving today's behavior.
+*/
+pub fn check_trade_authority(
+    market_authority: Option<Pubkey>,
+    user_owner: &Pubkey,
+    signer: &AccountInfo,
+    delegate_set: Option<&DelegateSet>,
+) -> Result<(), ProgramError> {
+    if !signer.is_signer {
+        msg!("A required signature is missing");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if signer.key == user_owner {
+        return Ok(());
+    }
+    let market_authority = match market_authority {
+        Some(a) => a,
+        None => {
+            msg!("Invalid user account owner provided!");
+            return Err(ProgramError::InvalidArgument);
+        }
+    };
+    if signer.key == &market_authority {
+        return Ok(());
+    }
+    if let Some(delegate_set) = delegate_set {
+        if delegate_set.is_authorized(signer.key) {
+            return Ok(());
+        }
+    }
+    msg!("The provided signer is neither the account owner nor an authorized delegate");
+    Err(ProgramError::InvalidArgument)
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct UserAccountHeader {
+    pub tag: AccountTag,
+    pub market: Pubkey,
+    pub owner: Pubkey,
+    pub base_token_free: u64,
+    pub base_token_locked: u64,
+    pub quote_token_free: u64,
+    pub quote_token_locked: u64,
+    pub accumulated_rebates: u64,
+    pub number_of_orders: u8,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct UserAccountState {
+    header: UserAccountHeader,
+    orders: Vec<u128>,
+    client_order_ids: Vec<u64>,
+}
+
+pub struct UserAccount<'a> {
+    pub header: UserAccountHeader,
+    orders: Vec<u128>,
+    client_order_ids: Vec<u64>,
+    account_info: &'a AccountInfo<'a>,
+}
+
+impl<'a> UserAccount<'a> {
+    pub fn parse(account_info: &'a AccountInfo<'a>) -> Result<Self, ProgramError> {
+        let state = UserAccountState::deserialize(&mut (&account_info.data.borrow() as &[u8]))?;
+        if state.header.tag == AccountTag::Closed {
+            msg!("This user account has already been closed");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if state.header.tag != AccountTag::UserAccount {
+            msg!("Invalid user account tag");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(Self {
+            header: state.header,
+            orders: state.orders,
+            client_order_ids: state.client_order_ids,
+            account_info,
+        })
+    }
+
+    pub fn read_order(&self, order_index: usize) -> Result<u128, ProgramError> {
+        self.orders.get(order_index).copied().ok_or_else(|| {
+            msg!("Invalid order index");
+            ProgramError::InvalidArgument
+        })
+    }
+
+    /**
+    Finds the slot of the first live order carrying the given client order id. Clients
+    use this to cancel an order without having tracked its on-chain `order_index`.
+    */
+    pub fn find_order_index_by_client_id(&self, client_order_id: u64) -> Result<usize, ProgramError> {
+        self.client_order_ids
+            .iter()
+            .position(|id| *id == client_order_id)
+            .ok_or_else(|| {
+                msg!("No order matches the provided client order id");
+                ProgramError::InvalidArgument
+            })
+    }
+
+    /**
+    Finds the slot holding a given on-chain order id, as reported by an AAOB
+    `Event::Out`. Used by `consume_events` to reconcile a canceled resting order
+    against the orders this account itself tracks.
+    */
+    pub fn find_order_index_by_id(&self, order_id: u128) -> Result<usize, ProgramError> {
+        self.orders
+            .iter()
+            .position(|id| *id == order_id)
+            .ok_or_else(|| {
+                msg!("No order matches the canceled order id");
+                ProgramError::InvalidArgument
+            })
+    }
+
+    pub fn add_order(&mut self, order_id: u128, client_order_id: u64) -> Result<(), ProgramError> {
+        if self.orders.len() >= MAX_USER_ORDERS {
+            msg!("This user account cannot hold any more open orders");
+            return Err(ProgramError::InvalidArgument);
+        }
+        self.orders.push(order_id);
+        self.client_order_ids.push(client_order_id);
+        self.header.number_of_orders += 1;
+        Ok(())
+    }
+
+    pub fn remove_order(&mut self, order_index: usize) -> Result<(), ProgramError> {
+        if order_index >= self.orders.len() {
+            msg!("Invalid order index");
+            return Err(ProgramError::InvalidArgument);
+        }
+        self.orders.remove(order_index);
+        self.client_order_ids.remove(order_index);
+        self.header.number_of_orders -= 1;
+        Ok(())
+    }
+
+    /**
+    Retires this user account ahead of a rent sweep: requires every token balance
+    to be zero and no orders left open, then marks the account `Closed` so a stale
+    reference is rejected by [`UserAccount::parse`] instead of being treated as an
+    empty valid account.
+    */
+    pub fn close(&mut self) -> Result<(), ProgramError> {
+        if self.header.base_token_free != 0
+            || self.header.base_token_locked != 0
+            || self.header.quote_token_free != 0
+            || self.header.quote_token_locked != 0
+        {
+            msg!("This user account still holds free or locked token balances");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if !self.orders.is_empty() {
+            msg!("This user account still has open orders");
+            return Err(ProgramError::InvalidArgument);
+        }
+        self.header.tag = AccountTag::Closed;
+        self.header.owner = Pubkey::default();
+        self.header.market = Pubkey::default();
+        Ok(())
+    }
+
+    pub fn write(&self) {
+        let state = UserAccountState {
+            header: UserAccountHeader {
+                tag: self.header.tag,
+                market: self.header.market,
+                owner: self.header.owner,
+                base_token_free: self.header.base_token_free,
+                base_token_locked: self.header.base_token_locked,
+                quote_token_free: self.header.quote_token_free,
+                quote_token_locked: self.header.quote_token_locked,
+                accumulated_rebates: self.header.accumulated_rebates,
+                number_of_orders: self.header.number_of_orders,
+            },
+            orders: self.orders.clone(),
+            client_order_ids: self.client_order_ids.clone(),
+        };
+        let mut data: &mut [u8] = &mut self.account_info.data.borrow_mut();
+        state.serialize(&mut data).unwrap();
+    }
+}
+
+/**
+Sweeps all lamports out of `account_info` into `destination`. Used to reclaim rent
+once an account's data has already been zeroed out and retired.
+*/
+pub fn sweep_lamports(account_info: &AccountInfo, destination: &AccountInfo) -> Result<(), ProgramError> {
+    let lamports = account_info.lamports();
+    **destination.lamports.borrow_mut() = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    **account_info.lamports.borrow_mut() = 0;
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use solana_program::{account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey};
+
+pub fn check_signer(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !account.is_signer {
+        msg!("A required signature is missing");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+pub fn check_account_key(account: &AccountInfo, key: &Pubkey) -> Result<(), ProgramError> {
+    if account.key != key {
+        msg!("An account does not match the expected key");
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(())
+}
+
+pub fn check_account_owner(account: &AccountInfo, owner: &Pubkey) -> Result<(), ProgramError> {
+    if account.owner != owner {
+        msg!("An account does not have the expected program owner");
+        return Err(ProgramError::IllegalOwner);
+    }
+    Ok(())
+}